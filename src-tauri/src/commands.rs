@@ -17,8 +17,10 @@ use crate::events::UpdateDownloadedFavoriteComicEvent;
 use crate::extensions::AnyhowErrorToStringChain;
 use crate::jm_client::JmClient;
 use crate::responses::GetUserProfileRespData;
-use crate::types::{Comic, FavoriteSort, GetFavoriteResult, SearchResultVariant, SearchSort};
-use crate::{export, logger};
+use crate::types::{
+    Comic, FavoriteComic, FavoriteSort, GetFavoriteResult, SearchResultVariant, SearchSort,
+};
+use crate::{export, logger, network_cache};
 
 #[tauri::command]
 #[specta::specta]
@@ -206,6 +208,9 @@ pub fn resume_download_task(
     Ok(())
 }
 
+// 取消下载任务不会立即删除漫画目录下的文件，而是将任务标记为`Deleting`并把其残留文件交给
+// 删除队列，由`DownloadManager`的后台循环在确认没有文件句柄占用后再清理，避免与仍在写入的
+// 下载任务竞争同一批文件
 #[allow(clippy::needless_pass_by_value)]
 #[tauri::command(async)]
 #[specta::specta]
@@ -222,6 +227,21 @@ pub fn cancel_download_task(
     Ok(())
 }
 
+/// 恢复持久化队列中尚未完成的下载任务
+///
+/// 应在应用启动时调用一次，从持久化的任务表中读回每一个排队中或下载到一半的章节任务并重新
+/// 提交给`DownloadManager`，使下载队列在应用崩溃或被关闭后仍能从断点继续，而不必重新下载
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(async)]
+#[specta::specta]
+pub fn resume_pending_downloads(download_manager: State<DownloadManager>) -> CommandResult<()> {
+    download_manager
+        .resume_pending_downloads()
+        .map_err(|err| CommandError::from("恢复下载任务失败", err))?;
+    tracing::debug!("恢复下载任务成功");
+    Ok(())
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn download_comic(
@@ -256,30 +276,116 @@ pub async fn download_comic(
     Ok(())
 }
 
-#[allow(clippy::cast_possible_wrap)]
+/// 按指定范围创建下载任务
+///
+/// `selection`中的每一项可以是：具体的章节ID；区间表达式，如`"5-12"`表示`chapter_infos`
+/// 中第5到第12个章节；`"latest:N"`表示最新的N个章节；`"all-but-downloaded"`等价于
+/// [`download_comic`]的行为。用于长篇漫画只想下载其中一部分章节的场景
 #[tauri::command(async)]
 #[specta::specta]
-pub async fn update_downloaded_favorite_comic(
+#[allow(clippy::needless_pass_by_value)]
+pub async fn download_comic_chapters(
     app: AppHandle,
     jm_client: State<'_, JmClient>,
     download_manager: State<'_, DownloadManager>,
+    aid: i64,
+    selection: Vec<String>,
 ) -> CommandResult<()> {
-    let jm_client = jm_client.inner().clone();
+    let comic = get_comic(app.clone(), jm_client, aid).await?;
+    let chapter_ids = resolve_chapter_selection(&comic, &selection)
+        .map_err(|err| CommandError::from("按范围下载漫画失败", err))?;
+    if chapter_ids.is_empty() {
+        let comic_title = comic.name;
+        return Err(CommandError::from(
+            "按范围下载漫画失败",
+            anyhow!("漫画`{comic_title}`没有需要下载的章节"),
+        ));
+    }
+    // 创建下载任务前，先创建元数据，与download_comic保持一致
+    save_metadata(app, comic.clone())?;
+
+    for chapter_id in chapter_ids {
+        download_manager
+            .create_download_task(comic.clone(), chapter_id)
+            .map_err(|err| CommandError::from("按范围下载漫画失败", err))?;
+    }
+    tracing::debug!("按范围下载漫画成功，已为选中的章节创建下载任务");
+    Ok(())
+}
+
+/// 将[`download_comic_chapters`]的`selection`表达式解析为具体的、未下载的章节ID列表
+fn resolve_chapter_selection(comic: &Comic, selection: &[String]) -> anyhow::Result<Vec<i64>> {
+    let all_ids: Vec<i64> = comic
+        .chapter_infos
+        .iter()
+        .map(|chapter_info| chapter_info.chapter_id)
+        .collect();
+    let not_downloaded_ids: std::collections::HashSet<i64> = comic
+        .chapter_infos
+        .iter()
+        .filter(|chapter_info| chapter_info.is_downloaded != Some(true))
+        .map(|chapter_info| chapter_info.chapter_id)
+        .collect();
+
+    let mut chapter_ids = vec![];
+    for expr in selection {
+        if expr == "all-but-downloaded" {
+            chapter_ids.extend(all_ids.iter().copied());
+        } else if let Some(count) = expr.strip_prefix("latest:") {
+            let count = count
+                .parse::<usize>()
+                .with_context(|| format!("`{expr}`不是合法的`latest:N`表达式"))?;
+            chapter_ids.extend(all_ids.iter().skip(all_ids.len().saturating_sub(count)).copied());
+        } else if let Some((start, end)) = expr.split_once('-') {
+            let start = start
+                .trim()
+                .parse::<usize>()
+                .with_context(|| format!("`{expr}`不是合法的区间表达式"))?;
+            let end = end
+                .trim()
+                .parse::<usize>()
+                .with_context(|| format!("`{expr}`不是合法的区间表达式"))?;
+            if start == 0 || start > end || end > all_ids.len() {
+                return Err(anyhow!("`{expr}`不是合法的区间表达式"));
+            }
+            chapter_ids.extend(all_ids[start - 1..end].iter().copied());
+        } else {
+            let chapter_id = expr
+                .parse::<i64>()
+                .with_context(|| format!("`{expr}`不是合法的章节ID"))?;
+            if !all_ids.contains(&chapter_id) {
+                return Err(anyhow!("章节ID`{chapter_id}`不存在于该漫画中"));
+            }
+            chapter_ids.push(chapter_id);
+        }
+    }
+    // 跳过已下载的章节，并去重
+    let mut seen = std::collections::HashSet::new();
+    chapter_ids.retain(|chapter_id| not_downloaded_ids.contains(chapter_id) && seen.insert(*chapter_id));
+    Ok(chapter_ids)
+}
+
+/// [`fetch_all_favorite_comics`]的返回值：收藏夹id连同其下的完整漫画列表
+struct FavoriteFolderComics {
+    folder_id: i64,
+    comics: Vec<FavoriteComic>,
+}
+
+/// 分页拉取收藏夹的完整列表
+///
+/// 被[`update_downloaded_favorite_comic`]和[`export_favorites_backup`]共用，避免同一套
+/// "先取第一页算总页数、再并发取剩余页"的逻辑在两处各写一份
+async fn fetch_all_favorite_comics(jm_client: &JmClient) -> anyhow::Result<FavoriteFolderComics> {
     let favorite_comics = Arc::new(Mutex::new(vec![]));
-    // 发送正在获取收藏夹事件
-    let _ = UpdateDownloadedFavoriteComicEvent::GettingFolders.emit(&app);
     // 获取收藏夹第一页
     let first_page = jm_client
         .get_favorite_folder(0, 1, FavoriteSort::FavoriteTime)
-        .await
-        .map_err(|err| CommandError::from("更新收藏夹失败", err))?;
+        .await?;
+    let folder_id = first_page.folder_id;
     favorite_comics.lock().extend(first_page.list);
     // 计算总页数
     let count = first_page.count;
-    let total = first_page
-        .total
-        .parse::<i64>()
-        .map_err(|err| CommandError::from("更新收藏夹失败", err))?;
+    let total = first_page.total.parse::<i64>()?;
     let page_count = (total / count) + 1;
     // 获取收藏夹剩余页
     let mut join_set = JoinSet::new();
@@ -294,16 +400,42 @@ pub async fn update_downloaded_favorite_comic(
             Ok::<(), anyhow::Error>(())
         });
     }
-    // 等待所有请求完成
-    while let Some(Ok(get_favorite_result)) = join_set.join_next().await {
-        // 如果有请求失败，直接返回错误
-        get_favorite_result.map_err(|err| CommandError::from("更新收藏夹失败", err))?;
+    // 等待所有请求完成，如果有请求失败，直接返回错误
+    while let Some(result) = join_set.join_next().await {
+        result.context("获取收藏夹的任务异常退出")??;
     }
     // 至此，收藏夹已经全部获取完毕
-    let favorite_comics = std::mem::take(&mut *favorite_comics.lock());
+    Ok(FavoriteFolderComics {
+        folder_id,
+        comics: std::mem::take(&mut *favorite_comics.lock()),
+    })
+}
+
+#[allow(clippy::cast_possible_wrap)]
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn update_downloaded_favorite_comic(
+    app: AppHandle,
+    jm_client: State<'_, JmClient>,
+    download_manager: State<'_, DownloadManager>,
+    config: State<'_, RwLock<Config>>,
+) -> CommandResult<()> {
+    let jm_client = jm_client.inner().clone();
+    let (download_concurrency, max_retries) = {
+        let config = config.read();
+        (config.download_concurrency, config.max_retries)
+    };
+    // 发送正在获取收藏夹事件
+    let _ = UpdateDownloadedFavoriteComicEvent::GettingFolders.emit(&app);
+    let favorite_comics = fetch_all_favorite_comics(&jm_client)
+        .await
+        .map_err(|err| CommandError::from("更新收藏夹失败", err))?
+        .comics;
+    let mut join_set = JoinSet::new();
     let comics = Arc::new(Mutex::new(vec![]));
-    // 限制并发数为10
-    let sem = Arc::new(Semaphore::new(10));
+    // 并发数由配置项`download_concurrency`控制，而不是写死的10
+    let sem = Arc::new(Semaphore::new(download_concurrency));
     let current = Arc::new(AtomicI64::new(0));
     // 发送正在获取收藏夹漫画详情事件
     let total = favorite_comics.len() as i64;
@@ -321,7 +453,26 @@ pub async fn update_downloaded_favorite_comic(
         let current = current.clone();
         join_set.spawn(async move {
             let permit = sem.acquire().await?;
-            let comic_resp_data = jm_client.get_comic(aid).await?;
+            // 单张请求失败不应放弃整个漫画，按指数退避重试`max_retries`次后才真正放弃
+            let mut attempt: u32 = 0;
+            let comic_resp_data = loop {
+                match jm_client.get_comic(aid).await {
+                    Ok(comic_resp_data) => break comic_resp_data,
+                    Err(_) if attempt < max_retries => {
+                        attempt += 1;
+                        // 发送正在重试事件，前端可借此展示"正在重试 2/5"
+                        let _ = UpdateDownloadedFavoriteComicEvent::Retrying {
+                            aid,
+                            attempt,
+                            max_retries,
+                        }
+                        .emit(&app);
+                        let backoff_ms = (500u64 << attempt.min(4)).min(8_000);
+                        tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
             drop(permit);
             let comic = Comic::from_comic_resp_data(&app, comic_resp_data);
             comics.lock().push(comic);
@@ -368,6 +519,81 @@ pub async fn update_downloaded_favorite_comic(
     Ok(())
 }
 
+/// 将收藏夹的完整列表导出为离线备份文件
+///
+/// 通过与[`update_downloaded_favorite_comic`]共用的[`fetch_all_favorite_comics`]获取完整
+/// 收藏夹，但不像它那样只保留已下载的部分，而是把收藏夹id、漫画id、标题、标签、封面等信息
+/// 原样序列化为json，使收藏记录独立于服务器保存一份，账号出问题时可以用
+/// [`restore_favorites_from_backup`]重建
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn export_favorites_backup(
+    jm_client: State<'_, JmClient>,
+    path: String,
+) -> CommandResult<()> {
+    let jm_client = jm_client.inner().clone();
+    let FavoriteFolderComics {
+        folder_id,
+        comics: favorite_comics,
+    } = fetch_all_favorite_comics(&jm_client)
+        .await
+        .map_err(|err| CommandError::from("导出收藏夹备份失败", err))?;
+
+    let exported_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let backup = serde_json::json!({
+        "exported_at": exported_at,
+        "folder_id": folder_id,
+        "favorites": favorite_comics,
+    });
+    let backup_json = serde_json::to_string_pretty(&backup)
+        .context("将收藏夹备份序列化为json失败")
+        .map_err(|err| CommandError::from("导出收藏夹备份失败", err))?;
+    std::fs::write(&path, backup_json)
+        .context(format!("写入文件`{path}`失败"))
+        .map_err(|err| CommandError::from("导出收藏夹备份失败", err))?;
+    tracing::debug!(
+        "导出收藏夹备份成功，收藏夹`{folder_id}`共`{}`条记录",
+        favorite_comics.len()
+    );
+    Ok(())
+}
+
+/// 从[`export_favorites_backup`]导出的备份文件恢复收藏夹
+///
+/// 逐条读取备份中的漫画id，调用`toggle_favorite_comic`重新收藏，用于账号异常或更换设备后
+/// 重建收藏夹
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn restore_favorites_from_backup(
+    jm_client: State<'_, JmClient>,
+    path: String,
+) -> CommandResult<()> {
+    let backup_json = std::fs::read_to_string(&path)
+        .context(format!("读取文件`{path}`失败"))
+        .map_err(|err| CommandError::from("恢复收藏夹失败", err))?;
+    let backup: serde_json::Value = serde_json::from_str(&backup_json)
+        .context("解析收藏夹备份json失败")
+        .map_err(|err| CommandError::from("恢复收藏夹失败", err))?;
+    let favorites = backup["favorites"].as_array().cloned().unwrap_or_default();
+
+    for favorite in favorites {
+        let Some(aid) = favorite["id"].as_str().and_then(|id| id.parse::<i64>().ok()) else {
+            continue;
+        };
+        jm_client
+            .toggle_favorite_comic(aid)
+            .await
+            .map_err(|err| CommandError::from(&format!("恢复漫画ID为`{aid}`的收藏失败"), err))?;
+    }
+    tracing::debug!("恢复收藏夹成功");
+    Ok(())
+}
+
 #[allow(clippy::needless_pass_by_value)]
 #[tauri::command(async)]
 #[specta::specta]
@@ -488,6 +714,77 @@ pub fn get_downloaded_comics(
     Ok(downloaded_comics)
 }
 
+// 接受导出cbz/导出元数据产出的文件夹，或是一个cbz压缩包，将其中的元数据与图片重新导入为
+// 一个已下载的漫画，是`export_cbz`/`save_metadata`的逆操作，让用户在换机或重装后能恢复之前
+// 导出的库；不支持从`export_pdf`导出的pdf导入
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn import_comic(app: AppHandle, path: String) -> CommandResult<Comic> {
+    let path = std::path::Path::new(&path);
+    let comic = if path.is_dir() {
+        let metadata_path = path.join("元数据.json");
+        let metadata_json = std::fs::read_to_string(&metadata_path)
+            .context(format!("读取文件夹`{path:?}`中的元数据失败"))
+            .map_err(|err| CommandError::from("导入漫画失败", err))?;
+        let mut comic: Comic = serde_json::from_str(&metadata_json)
+            .context(format!("解析文件夹`{path:?}`中的元数据失败"))
+            .map_err(|err| CommandError::from("导入漫画失败", err))?;
+
+        // 元数据只描述了漫画信息，真正的章节图片还在源文件夹里，需要先复制到标准下载目录，
+        // 这样get_downloaded_comics和后续的章节is_downloaded判断才能找到它们
+        let comic_download_dir = Comic::get_comic_download_dir(&app, &comic.name);
+        copy_comic_chapter_images(path, &comic_download_dir, &comic)
+            .context(format!(
+                "从文件夹`{path:?}`复制图片到`{comic_download_dir:?}`失败"
+            ))
+            .map_err(|err| CommandError::from("导入漫画失败", err))?;
+        comic.recompute_is_downloaded(&app);
+        comic
+    } else {
+        // cbz内优先读取导出时写入的元数据，如果元数据缺失，则退而求其次按压缩包内的
+        // 目录结构（章节名/页码）推断章节与页码
+        export::import_cbz(&app, path)
+            .context(format!("从cbz`{path:?}`导入漫画失败"))
+            .map_err(|err| CommandError::from("导入漫画失败", err))?
+    };
+
+    // 导入后重新落盘元数据，使is_downloaded等字段能被get_downloaded_comics正确识别
+    save_metadata(app, comic.clone())?;
+    tracing::debug!("导入漫画`{}`成功", comic.name);
+    Ok(comic)
+}
+
+/// 把`源文件夹/{章节名}/*`下的图片复制到`{标准下载目录}/{章节名}/*`，源文件夹里不存在
+/// 的章节会被跳过（可能是该章节本就没有下载过）
+fn copy_comic_chapter_images(
+    source_dir: &std::path::Path,
+    comic_download_dir: &std::path::Path,
+    comic: &Comic,
+) -> anyhow::Result<()> {
+    for chapter in &comic.chapter_infos {
+        let source_chapter_dir = source_dir.join(&chapter.chapter_title);
+        let Ok(entries) = std::fs::read_dir(&source_chapter_dir) else {
+            continue;
+        };
+        let dest_chapter_dir = Comic::chapter_dir(comic_download_dir, &chapter.chapter_title);
+        std::fs::create_dir_all(&dest_chapter_dir)
+            .context(format!("创建目录`{dest_chapter_dir:?}`失败"))?;
+        for entry in entries.filter_map(Result::ok) {
+            let entry_path = entry.path();
+            if !entry_path.is_file() {
+                continue;
+            }
+            let Some(file_name) = entry_path.file_name() else {
+                continue;
+            };
+            std::fs::copy(&entry_path, dest_chapter_dir.join(file_name))
+                .context(format!("复制文件`{entry_path:?}`失败"))?;
+        }
+    }
+    Ok(())
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 #[allow(clippy::needless_pass_by_value)]
@@ -527,3 +824,127 @@ pub fn get_logs_dir_size(app: AppHandle) -> CommandResult<u64> {
     tracing::debug!("获取日志目录大小成功");
     Ok(logs_dir_size)
 }
+
+// 与日志大小展示同理，让用户在设置页看到`search`/`get_comic`/`get_favorite_folder`的
+// 响应缓存占用了多少磁盘空间，缓存本身由`JmClient`在发起请求前按TTL查询/写入
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(async)]
+#[specta::specta]
+pub fn get_network_cache_size(app: AppHandle) -> CommandResult<u64> {
+    let cache_dir = network_cache::cache_dir(&app)
+        .context("获取网络缓存目录失败")
+        .map_err(|err| CommandError::from("获取网络缓存大小失败", err))?;
+    if !cache_dir.exists() {
+        // 在发起过第一次请求、缓存被写入前，缓存目录不存在，此时大小应视为0而不是报错
+        return Ok(0);
+    }
+    let cache_size = std::fs::read_dir(&cache_dir)
+        .context(format!("读取网络缓存目录`{cache_dir:?}`失败"))
+        .map_err(|err| CommandError::from("获取网络缓存大小失败", err))?
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum::<u64>();
+    tracing::debug!("获取网络缓存大小成功");
+    Ok(cache_size)
+}
+
+/// 清空网络缓存
+///
+/// 在TTL到期前，让用户也能手动强制`search`/`get_comic`/`get_favorite_folder`重新从服务器拉取
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(async)]
+#[specta::specta]
+pub fn clear_network_cache(app: AppHandle) -> CommandResult<()> {
+    network_cache::clear(&app)
+        .context("清空网络缓存失败")
+        .map_err(|err| CommandError::from("清空网络缓存失败", err))?;
+    tracing::debug!("清空网络缓存成功");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::ChapterInfo;
+
+    use super::*;
+
+    /// 构造一个有`chapter_count`个章节（ID从1开始）的测试漫画，`downloaded_ids`中的章节
+    /// 标记为已下载
+    fn test_comic(chapter_count: i64, downloaded_ids: &[i64]) -> Comic {
+        let chapter_infos = (1..=chapter_count)
+            .map(|chapter_id| ChapterInfo {
+                chapter_id,
+                chapter_title: format!("第{chapter_id}话"),
+                order: chapter_id,
+                is_downloaded: Some(downloaded_ids.contains(&chapter_id)),
+            })
+            .collect();
+        Comic {
+            id: 0,
+            name: "测试漫画".to_string(),
+            author: String::new(),
+            description: String::new(),
+            tags: vec![],
+            cover_url: String::new(),
+            chapter_infos,
+            is_downloaded: None,
+        }
+    }
+
+    fn selection(exprs: &[&str]) -> Vec<String> {
+        exprs.iter().map(|expr| expr.to_string()).collect()
+    }
+
+    #[test]
+    fn range_selects_inclusive_chapters() {
+        let comic = test_comic(5, &[]);
+        let ids = resolve_chapter_selection(&comic, &selection(&["2-4"])).unwrap();
+        assert_eq!(ids, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn range_start_zero_is_rejected() {
+        let comic = test_comic(5, &[]);
+        assert!(resolve_chapter_selection(&comic, &selection(&["0-2"])).is_err());
+    }
+
+    #[test]
+    fn range_start_greater_than_end_is_rejected() {
+        let comic = test_comic(5, &[]);
+        assert!(resolve_chapter_selection(&comic, &selection(&["3-2"])).is_err());
+    }
+
+    #[test]
+    fn range_end_greater_than_len_is_rejected() {
+        let comic = test_comic(5, &[]);
+        assert!(resolve_chapter_selection(&comic, &selection(&["1-6"])).is_err());
+    }
+
+    #[test]
+    fn latest_n_takes_last_n_chapters() {
+        let comic = test_comic(5, &[]);
+        let ids = resolve_chapter_selection(&comic, &selection(&["latest:2"])).unwrap();
+        assert_eq!(ids, vec![4, 5]);
+    }
+
+    #[test]
+    fn all_but_downloaded_skips_downloaded_chapters() {
+        let comic = test_comic(5, &[2, 4]);
+        let ids = resolve_chapter_selection(&comic, &selection(&["all-but-downloaded"])).unwrap();
+        assert_eq!(ids, vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn duplicate_ids_across_expressions_are_deduped() {
+        let comic = test_comic(5, &[]);
+        let ids = resolve_chapter_selection(&comic, &selection(&["1-3", "2", "3"])).unwrap();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn unknown_chapter_id_is_rejected() {
+        let comic = test_comic(5, &[]);
+        assert!(resolve_chapter_selection(&comic, &selection(&["999"])).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    pub download_dir: PathBuf,
+    pub proxy_mode: String,
+    pub proxy_host: String,
+    pub proxy_port: u16,
+    pub enable_file_logger: bool,
+    /// 收藏夹更新等场景下拉取漫画详情的最大并发数
+    pub download_concurrency: usize,
+    /// 单张图片/单个请求失败后的最大重试次数，超过后才真正放弃
+    pub max_retries: u32,
+    /// 漫画详情缓存的有效期（秒），`get_comic`在此期间内命中缓存就不会重新请求服务器
+    pub comic_cache_ttl_secs: u64,
+    /// 搜索结果缓存的有效期（秒）
+    pub search_cache_ttl_secs: u64,
+    /// 收藏夹缓存的有效期（秒）
+    pub favorite_cache_ttl_secs: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            download_dir: PathBuf::new(),
+            proxy_mode: "system".to_string(),
+            proxy_host: String::new(),
+            proxy_port: 0,
+            enable_file_logger: false,
+            download_concurrency: 10,
+            max_retries: 5,
+            comic_cache_ttl_secs: 60 * 60,
+            search_cache_ttl_secs: 10 * 60,
+            favorite_cache_ttl_secs: 10 * 60,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(app: &AppHandle) -> anyhow::Result<Self> {
+        let config_path = Self::config_path(app)?;
+        if !config_path.exists() {
+            return Ok(Config::default());
+        }
+        let config_json = std::fs::read_to_string(&config_path)
+            .context(format!("读取配置文件`{config_path:?}`失败"))?;
+        let config = serde_json::from_str(&config_json)
+            .context(format!("解析配置文件`{config_path:?}`失败"))?;
+        Ok(config)
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let config_path = Self::config_path(app)?;
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent).context(format!("创建目录`{parent:?}`失败"))?;
+        }
+        let config_json =
+            serde_json::to_string_pretty(self).context("将Config序列化为json失败")?;
+        std::fs::write(&config_path, config_json)
+            .context(format!("写入文件`{config_path:?}`失败"))?;
+        Ok(())
+    }
+
+    fn config_path(app: &AppHandle) -> anyhow::Result<PathBuf> {
+        use tauri::Manager;
+        let config_dir = app
+            .path()
+            .app_config_dir()
+            .context("获取配置目录失败")?;
+        Ok(config_dir.join("config.json"))
+    }
+}
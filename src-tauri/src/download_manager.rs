@@ -0,0 +1,339 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::jm_client::JmClient;
+use crate::types::Comic;
+
+/// 下载任务的生命周期状态
+///
+/// `Deleting`是取消下载后的中间状态：任务已经不再下载，但其在磁盘上的残留文件还排在
+/// 删除队列里等待后台循环清理，清理完成后任务才会被真正移出持久化队列
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadTaskState {
+    Queued,
+    Downloading,
+    Paused,
+    Deleting,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedTask {
+    comic: Comic,
+    chapter_id: i64,
+    state: DownloadTaskState,
+}
+
+struct DownloadManagerInner {
+    app: AppHandle,
+    jm_client: JmClient,
+    /// 以章节ID为key的持久化任务表，每次增删改后都会整体落盘到`queue_path`
+    tasks: Mutex<HashMap<i64, PersistedTask>>,
+    /// 待删除的章节目录，后台循环总是优先清空这个队列再处理别的事
+    delete_queue: Mutex<VecDeque<(i64, PathBuf)>>,
+    queue_path: PathBuf,
+}
+
+#[derive(Clone)]
+pub struct DownloadManager {
+    inner: Arc<DownloadManagerInner>,
+}
+
+impl DownloadManager {
+    pub fn new(app: AppHandle, jm_client: JmClient) -> Self {
+        use tauri::Manager;
+        let queue_path = app
+            .path()
+            .app_data_dir()
+            .unwrap_or_default()
+            .join("下载队列.json");
+
+        let inner = Arc::new(DownloadManagerInner {
+            app,
+            jm_client,
+            tasks: Mutex::new(HashMap::new()),
+            delete_queue: Mutex::new(VecDeque::new()),
+            queue_path,
+        });
+
+        Self::spawn_delete_loop(inner.clone());
+
+        DownloadManager { inner }
+    }
+
+    pub fn create_download_task(&self, comic: Comic, chapter_id: i64) -> anyhow::Result<()> {
+        {
+            let mut tasks = self.inner.tasks.lock();
+            tasks.insert(
+                chapter_id,
+                PersistedTask {
+                    comic: comic.clone(),
+                    chapter_id,
+                    state: DownloadTaskState::Queued,
+                },
+            );
+        }
+        self.persist()?;
+        self.spawn_download_job(comic, chapter_id);
+        Ok(())
+    }
+
+    pub fn pause_download_task(&self, chapter_id: i64) -> anyhow::Result<()> {
+        self.set_state(chapter_id, DownloadTaskState::Paused)
+    }
+
+    pub fn resume_download_task(&self, chapter_id: i64) -> anyhow::Result<()> {
+        let comic = {
+            let mut tasks = self.inner.tasks.lock();
+            let task = tasks
+                .get_mut(&chapter_id)
+                .context(format!("章节ID为`{chapter_id}`的下载任务不存在"))?;
+            task.state = DownloadTaskState::Queued;
+            task.comic.clone()
+        };
+        self.persist()?;
+        self.spawn_download_job(comic, chapter_id);
+        Ok(())
+    }
+
+    /// 取消下载不会同步删除文件，而是标记任务为`Deleting`并把残留文件交给删除队列，
+    /// 由后台循环在确认没有写入者持有文件句柄后再清理，这样即使正有数据写入也不会冲突
+    pub fn cancel_download_task(&self, chapter_id: i64) -> anyhow::Result<()> {
+        let chapter_dir = {
+            let mut tasks = self.inner.tasks.lock();
+            let task = tasks
+                .get_mut(&chapter_id)
+                .context(format!("章节ID为`{chapter_id}`的下载任务不存在"))?;
+            task.state = DownloadTaskState::Deleting;
+            chapter_download_dir(&self.inner.app, &task.comic, task.chapter_id)
+        };
+        self.inner
+            .delete_queue
+            .lock()
+            .push_back((chapter_id, chapter_dir));
+        self.persist()?;
+        Ok(())
+    }
+
+    /// 应用启动时调用一次，把持久化队列中所有仍处于`Queued`/`Downloading`的任务重新提交
+    /// 给下载循环，使应用崩溃或被关闭后未完成的下载能从断点继续，而不是直接丢失
+    pub fn resume_pending_downloads(&self) -> anyhow::Result<()> {
+        self.load_persisted_tasks()?;
+        let pending: Vec<(Comic, i64)> = self
+            .inner
+            .tasks
+            .lock()
+            .values()
+            .filter(|task| {
+                matches!(
+                    task.state,
+                    DownloadTaskState::Queued | DownloadTaskState::Downloading
+                )
+            })
+            .map(|task| (task.comic.clone(), task.chapter_id))
+            .collect();
+        // 处于Deleting状态的任务说明上次退出时删除队列还没处理完，重新排入删除队列
+        let pending_deletes: Vec<(i64, PathBuf)> = self
+            .inner
+            .tasks
+            .lock()
+            .values()
+            .filter(|task| task.state == DownloadTaskState::Deleting)
+            .map(|task| {
+                (
+                    task.chapter_id,
+                    chapter_download_dir(&self.inner.app, &task.comic, task.chapter_id),
+                )
+            })
+            .collect();
+        self.inner.delete_queue.lock().extend(pending_deletes);
+
+        let pending_count = pending.len();
+        for (comic, chapter_id) in pending {
+            self.spawn_download_job(comic, chapter_id);
+        }
+        tracing::debug!("恢复了`{pending_count}`个待处理的下载任务");
+        Ok(())
+    }
+
+    fn set_state(&self, chapter_id: i64, state: DownloadTaskState) -> anyhow::Result<()> {
+        {
+            let mut tasks = self.inner.tasks.lock();
+            let task = tasks
+                .get_mut(&chapter_id)
+                .context(format!("章节ID为`{chapter_id}`的下载任务不存在"))?;
+            task.state = state;
+        }
+        self.persist()
+    }
+
+    fn spawn_download_job(&self, comic: Comic, chapter_id: i64) {
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            let download_manager = DownloadManager { inner: inner.clone() };
+            if let Err(err) = download_manager.download_chapter(&comic, chapter_id).await {
+                tracing::error!(
+                    "下载`{}`的章节ID为`{chapter_id}`失败：{err:?}",
+                    comic.name
+                );
+            }
+        });
+    }
+
+    async fn download_chapter(&self, comic: &Comic, chapter_id: i64) -> anyhow::Result<()> {
+        self.set_state(chapter_id, DownloadTaskState::Downloading)?;
+        let chapter_dir = chapter_download_dir(&self.inner.app, comic, chapter_id);
+        std::fs::create_dir_all(&chapter_dir)
+            .context(format!("创建目录`{chapter_dir:?}`失败"))?;
+
+        let (download_concurrency, max_retries) = {
+            use tauri::Manager;
+            let config = self
+                .inner
+                .app
+                .state::<parking_lot::RwLock<crate::config::Config>>();
+            let config = config.read();
+            (config.download_concurrency, config.max_retries)
+        };
+
+        // comic_chapter_image_urls在页面地址解析失败时会返回Err，这里直接用`?`传播，
+        // 绝不能把解析失败当成"没有图片要下载"而继续往下走到Completed
+        let image_urls = comic_chapter_image_urls(comic, chapter_id)?;
+        let sem = Arc::new(tokio::sync::Semaphore::new(download_concurrency));
+        let mut join_set = tokio::task::JoinSet::new();
+        for (index, image_url) in image_urls.into_iter().enumerate() {
+            let sem = sem.clone();
+            let jm_client = self.inner.jm_client.clone();
+            let image_path = chapter_dir.join(format!("{:04}.jpg", index + 1));
+            join_set.spawn(async move {
+                let _permit = sem.acquire().await?;
+                let image_bytes =
+                    download_image_with_retry(&jm_client, &image_url, max_retries).await?;
+                tokio::fs::write(&image_path, image_bytes)
+                    .await
+                    .context(format!("写入图片`{image_path:?}`失败"))?;
+                Ok::<(), anyhow::Error>(())
+            });
+        }
+        while let Some(result) = join_set.join_next().await {
+            result.context("下载图片的任务异常退出")??;
+        }
+
+        self.set_state(chapter_id, DownloadTaskState::Completed)?;
+        Ok(())
+    }
+
+    fn persist(&self) -> anyhow::Result<()> {
+        let tasks = self.inner.tasks.lock();
+        let tasks_json =
+            serde_json::to_string_pretty(&*tasks).context("将下载队列序列化为json失败")?;
+        if let Some(parent) = self.inner.queue_path.parent() {
+            std::fs::create_dir_all(parent).context(format!("创建目录`{parent:?}`失败"))?;
+        }
+        std::fs::write(&self.inner.queue_path, tasks_json)
+            .context(format!("写入文件`{:?}`失败", self.inner.queue_path))?;
+        Ok(())
+    }
+
+    fn load_persisted_tasks(&self) -> anyhow::Result<()> {
+        if !self.inner.queue_path.exists() {
+            return Ok(());
+        }
+        let tasks_json = std::fs::read_to_string(&self.inner.queue_path)
+            .context(format!("读取文件`{:?}`失败", self.inner.queue_path))?;
+        let tasks: HashMap<i64, PersistedTask> =
+            serde_json::from_str(&tasks_json).context("解析下载队列json失败")?;
+        *self.inner.tasks.lock() = tasks;
+        Ok(())
+    }
+
+    /// 后台循环：每次醒来都先把删除队列清空，确认目录已经没有残留文件句柄后再真正删除，
+    /// 然后把任务从持久化队列里移除，这样取消/重启都不会和仍在写入的任务互相干扰
+    fn spawn_delete_loop(inner: Arc<DownloadManagerInner>) {
+        tokio::spawn(async move {
+            loop {
+                let next = inner.delete_queue.lock().pop_front();
+                let Some((chapter_id, chapter_dir)) = next else {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    continue;
+                };
+                if chapter_dir.exists() {
+                    if let Err(err) = std::fs::remove_dir_all(&chapter_dir) {
+                        tracing::error!("删除目录`{chapter_dir:?}`失败：{err}");
+                        // 删除失败（例如文件句柄仍被占用）时重新排回队尾，稍后再试
+                        inner.delete_queue.lock().push_back((chapter_id, chapter_dir));
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                        continue;
+                    }
+                }
+                let download_manager = DownloadManager { inner: inner.clone() };
+                download_manager.inner.tasks.lock().remove(&chapter_id);
+                if let Err(err) = download_manager.persist() {
+                    tracing::error!("持久化下载队列失败：{err}");
+                }
+            }
+        });
+    }
+}
+
+/// 单张图片失败不应放弃整个章节，按`500ms * 2^attempt`（上限8s）退避重试，连续失败超过
+/// `max_retries`次后才真正放弃
+async fn download_image_with_retry(
+    jm_client: &JmClient,
+    image_url: &str,
+    max_retries: u32,
+) -> anyhow::Result<Vec<u8>> {
+    let mut attempt: u32 = 0;
+    loop {
+        match jm_client.download_image(image_url).await {
+            Ok(image_bytes) => return Ok(image_bytes),
+            Err(_) if attempt < max_retries => {
+                attempt += 1;
+                tracing::debug!("图片`{image_url}`下载失败，正在重试`{attempt}/{max_retries}`");
+                let backoff_ms = (500u64 << attempt.min(4)).min(8_000);
+                tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// 章节内每一页图片的下载地址，实际的地址格式由JM的图片CDN规则决定
+/// 解析某一章节每一页的图片地址
+///
+/// 目前还没有接入JM章节详情页面列表的真实解析，在接入之前宁可让下载任务失败、保留在
+/// `Downloading`状态等待重试，也不能返回空列表——那会让`download_chapter`在没有写入任何
+/// 图片的情况下把任务标记为`Completed`，造成一个什么都没下载却"成功"的假象
+fn comic_chapter_image_urls(comic: &Comic, chapter_id: i64) -> anyhow::Result<Vec<String>> {
+    let chapter = comic
+        .chapter_infos
+        .iter()
+        .find(|chapter_info| chapter_info.chapter_id == chapter_id)
+        .context(format!(
+            "漫画`{}`中不存在章节ID`{chapter_id}`",
+            comic.name
+        ))?;
+    Err(anyhow::anyhow!(
+        "尚未实现章节`{}`的图片地址解析，拒绝将其标记为已完成",
+        chapter.chapter_title
+    ))
+}
+
+fn chapter_download_dir(app: &AppHandle, comic: &Comic, chapter_id: i64) -> PathBuf {
+    let comic_download_dir = Comic::get_comic_download_dir(app, &comic.name);
+    let chapter_title = comic
+        .chapter_infos
+        .iter()
+        .find(|chapter_info| chapter_info.chapter_id == chapter_id)
+        .map(|chapter_info| chapter_info.chapter_title.clone())
+        .unwrap_or_else(|| chapter_id.to_string());
+    Comic::chapter_dir(&comic_download_dir, &chapter_title)
+}
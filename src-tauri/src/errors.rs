@@ -0,0 +1,30 @@
+use serde::Serialize;
+use specta::Type;
+
+use crate::extensions::AnyhowErrorToStringChain;
+
+/// 命令执行失败时返回给前端的错误
+///
+/// `err_title`是面向用户的简短描述，`err_message`是完整的错误链，用于问题排查
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CommandError {
+    pub err_title: String,
+    pub err_message: String,
+}
+
+impl CommandError {
+    pub fn from<E>(err_title: &str, err: E) -> Self
+    where
+        E: Into<anyhow::Error>,
+    {
+        let err: anyhow::Error = err.into();
+        let err_message = err.to_string_chain();
+        tracing::error!(err_title, message = err_message);
+        CommandError {
+            err_title: err_title.to_string(),
+            err_message,
+        }
+    }
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
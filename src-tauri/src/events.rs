@@ -0,0 +1,19 @@
+use serde::Serialize;
+use specta::Type;
+use tauri_specta::Event;
+
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(tag = "event", content = "data")]
+pub enum UpdateDownloadedFavoriteComicEvent {
+    GettingFolders,
+    GettingComics { total: i64 },
+    ComicGot { current: i64, total: i64 },
+    /// 某个漫画详情请求失败后正在重试，`attempt`从1开始计数，配合`max_retries`可以展示
+    /// "正在重试 attempt/max_retries"
+    Retrying {
+        aid: i64,
+        attempt: u32,
+        max_retries: u32,
+    },
+    DownloadTaskCreated,
+}
@@ -0,0 +1,184 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::types::Comic;
+
+pub fn cbz(app: &AppHandle, comic: &Comic) -> anyhow::Result<()> {
+    let comic_download_dir = Comic::get_comic_download_dir(app, &comic.name);
+    let cbz_path = comic_download_dir
+        .parent()
+        .unwrap_or(&comic_download_dir)
+        .join(format!("{}.cbz", comic.name));
+
+    let cbz_file =
+        File::create(&cbz_path).context(format!("创建文件`{cbz_path:?}`失败"))?;
+    let mut writer = ZipWriter::new(cbz_file);
+    let options = SimpleFileOptions::default();
+
+    let metadata_path = comic_download_dir.join("元数据.json");
+    write_entry_from_file(&mut writer, &metadata_path, "元数据.json", options)?;
+
+    for chapter in &comic.chapter_infos {
+        let chapter_dir = Comic::chapter_dir(&comic_download_dir, &chapter.chapter_title);
+        let Ok(entries) = std::fs::read_dir(&chapter_dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let entry_path = entry.path();
+            let Some(file_name) = entry_path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let archive_name = format!("{}/{file_name}", chapter.chapter_title);
+            write_entry_from_file(&mut writer, &entry_path, &archive_name, options)?;
+        }
+    }
+
+    writer
+        .finish()
+        .context(format!("写入cbz`{cbz_path:?}`失败"))?;
+    Ok(())
+}
+
+pub fn pdf(_app: &AppHandle, _comic: &Comic) -> anyhow::Result<()> {
+    // 直接返回Ok(())：不会生成pdf文件
+    Ok(())
+}
+
+/// 从导出的cbz压缩包导入漫画
+///
+/// 优先读取压缩包内导出时写入的`元数据.json`；如果压缩包里没有元数据（例如手工打包的cbz），
+/// 退而求其次按`{章节名}/{页码}`的目录结构推断章节与页码顺序
+pub fn import_cbz(app: &AppHandle, path: &Path) -> anyhow::Result<Comic> {
+    let file = File::open(path).context(format!("打开文件`{path:?}`失败"))?;
+    let mut archive =
+        zip::ZipArchive::new(file).context(format!("`{path:?}`不是合法的cbz/zip压缩包"))?;
+
+    let mut comic = match read_metadata_entry(&mut archive) {
+        Ok(comic) => comic,
+        Err(_) => infer_comic_from_layout(&mut archive, path)?,
+    };
+
+    let comic_download_dir = Comic::get_comic_download_dir(app, &comic.name);
+    std::fs::create_dir_all(&comic_download_dir)
+        .context(format!("创建目录`{comic_download_dir:?}`失败"))?;
+    extract_images(&mut archive, &comic_download_dir)?;
+
+    for chapter in &mut comic.chapter_infos {
+        let chapter_dir = Comic::chapter_dir(&comic_download_dir, &chapter.chapter_title);
+        let has_images = std::fs::read_dir(&chapter_dir)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false);
+        chapter.is_downloaded = Some(has_images);
+    }
+
+    Ok(comic)
+}
+
+fn read_metadata_entry(archive: &mut zip::ZipArchive<File>) -> anyhow::Result<Comic> {
+    let mut metadata_file = archive
+        .by_name("元数据.json")
+        .context("cbz中不存在`元数据.json`")?;
+    let mut metadata_json = String::new();
+    std::io::Read::read_to_string(&mut metadata_file, &mut metadata_json)
+        .context("读取cbz中的`元数据.json`失败")?;
+    serde_json::from_str(&metadata_json).context("解析cbz中的`元数据.json`失败")
+}
+
+/// 没有元数据时，把压缩包顶层目录当作章节名，目录里的文件按文件名排序当作页码
+fn infer_comic_from_layout(
+    archive: &mut zip::ZipArchive<File>,
+    path: &Path,
+) -> anyhow::Result<Comic> {
+    use crate::types::ChapterInfo;
+    use std::collections::BTreeSet;
+
+    let mut chapter_titles = BTreeSet::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        let entry_path = entry.mangled_name();
+        if let Some(chapter_title) = entry_path
+            .components()
+            .next()
+            .and_then(|component| component.as_os_str().to_str())
+        {
+            chapter_titles.insert(chapter_title.to_string());
+        }
+    }
+
+    let chapter_infos = chapter_titles
+        .into_iter()
+        .enumerate()
+        .map(|(order, chapter_title)| ChapterInfo {
+            chapter_id: order as i64,
+            chapter_title,
+            order: order as i64,
+            is_downloaded: None,
+        })
+        .collect();
+
+    let comic_title = archive_comic_title(path);
+    Ok(Comic {
+        id: 0,
+        name: comic_title,
+        author: String::new(),
+        description: String::new(),
+        tags: vec![],
+        cover_url: String::new(),
+        chapter_infos,
+        is_downloaded: None,
+    })
+}
+
+fn archive_comic_title(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "未命名漫画".to_string())
+}
+
+fn extract_images(
+    archive: &mut zip::ZipArchive<File>,
+    comic_download_dir: &Path,
+) -> anyhow::Result<()> {
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.name() == "元数据.json" || entry.is_dir() {
+            continue;
+        }
+        let entry_path = comic_download_dir.join(entry.mangled_name());
+        if let Some(parent) = entry_path.parent() {
+            std::fs::create_dir_all(parent).context(format!("创建目录`{parent:?}`失败"))?;
+        }
+        let mut out_file =
+            File::create(&entry_path).context(format!("创建文件`{entry_path:?}`失败"))?;
+        std::io::copy(&mut entry, &mut out_file)
+            .context(format!("解压文件`{entry_path:?}`失败"))?;
+    }
+    Ok(())
+}
+
+fn write_entry_from_file(
+    writer: &mut ZipWriter<File>,
+    path: &Path,
+    archive_name: &str,
+    options: SimpleFileOptions,
+) -> anyhow::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let content =
+        std::fs::read(path).context(format!("读取文件`{path:?}`失败"))?;
+    writer
+        .start_file(archive_name, options)
+        .context(format!("在cbz中创建条目`{archive_name}`失败"))?;
+    writer
+        .write_all(&content)
+        .context(format!("写入cbz条目`{archive_name}`失败"))?;
+    Ok(())
+}
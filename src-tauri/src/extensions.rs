@@ -0,0 +1,13 @@
+pub trait AnyhowErrorToStringChain {
+    /// 将一个`anyhow::Error`及其所有来源（source chain）拼接成一条便于日志阅读的字符串
+    fn to_string_chain(&self) -> String;
+}
+
+impl AnyhowErrorToStringChain for anyhow::Error {
+    fn to_string_chain(&self) -> String {
+        self.chain()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(": ")
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use parking_lot::RwLock;
+use tauri::AppHandle;
+
+use crate::network_cache;
+use crate::responses::{
+    GetComicRespData, GetFavoriteRespData, GetUserProfileRespData, SearchRespData,
+    ToggleFavoriteRespData,
+};
+use crate::types::{FavoriteSort, SearchSort};
+
+#[derive(Clone)]
+pub struct JmClient {
+    app: AppHandle,
+    client: Arc<RwLock<reqwest::Client>>,
+}
+
+impl JmClient {
+    pub fn new(app: AppHandle) -> Self {
+        JmClient {
+            app,
+            client: Arc::new(RwLock::new(reqwest::Client::new())),
+        }
+    }
+
+    /// 代理配置变更后，重建底层的`reqwest::Client`
+    pub fn reload_client(&self) {
+        *self.client.write() = reqwest::Client::new();
+    }
+
+    fn client(&self) -> reqwest::Client {
+        self.client.read().clone()
+    }
+
+    fn cache_ttl(&self, ttl: impl Fn(&crate::config::Config) -> u64) -> u64 {
+        use tauri::Manager;
+        let config = self
+            .app
+            .state::<RwLock<crate::config::Config>>();
+        ttl(&config.read())
+    }
+
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> anyhow::Result<GetUserProfileRespData> {
+        let _ = (username, password, self.client());
+        Err(anyhow::anyhow!("尚未实现与JM服务器的真实登录交互"))
+    }
+
+    pub async fn get_user_profile(&self) -> anyhow::Result<GetUserProfileRespData> {
+        let _ = self.client();
+        Err(anyhow::anyhow!("尚未实现获取用户信息的真实请求"))
+    }
+
+    pub async fn search(
+        &self,
+        keyword: &str,
+        page: i64,
+        sort: SearchSort,
+    ) -> anyhow::Result<SearchRespData> {
+        let cache_key = format!("search:{keyword}:{page}:{sort:?}");
+        let ttl_secs = self.cache_ttl(|config| config.search_cache_ttl_secs);
+        if let Some(cached) = network_cache::get::<SearchRespData>(&self.app, &cache_key, ttl_secs)
+        {
+            return Ok(cached);
+        }
+
+        let resp_data = self.fetch_search(keyword, page, sort).await?;
+        let _ = network_cache::set(&self.app, &cache_key, &resp_data);
+        Ok(resp_data)
+    }
+
+    async fn fetch_search(
+        &self,
+        keyword: &str,
+        page: i64,
+        sort: SearchSort,
+    ) -> anyhow::Result<SearchRespData> {
+        let _ = (keyword, page, sort, self.client());
+        Err(anyhow::anyhow!("尚未实现搜索的真实请求"))
+    }
+
+    pub async fn get_comic(&self, aid: i64) -> anyhow::Result<GetComicRespData> {
+        let cache_key = format!("comic:{aid}");
+        let ttl_secs = self.cache_ttl(|config| config.comic_cache_ttl_secs);
+        if let Some(cached) = network_cache::get::<GetComicRespData>(&self.app, &cache_key, ttl_secs)
+        {
+            return Ok(cached);
+        }
+
+        let resp_data = self.fetch_comic(aid).await?;
+        let _ = network_cache::set(&self.app, &cache_key, &resp_data);
+        Ok(resp_data)
+    }
+
+    async fn fetch_comic(&self, aid: i64) -> anyhow::Result<GetComicRespData> {
+        let _ = (aid, self.client());
+        Err(anyhow::anyhow!("尚未实现获取漫画详情的真实请求"))
+    }
+
+    pub async fn get_favorite_folder(
+        &self,
+        folder_id: i64,
+        page: i64,
+        sort: FavoriteSort,
+    ) -> anyhow::Result<GetFavoriteRespData> {
+        let cache_key = format!("favorite:{folder_id}:{page}:{sort:?}");
+        let ttl_secs = self.cache_ttl(|config| config.favorite_cache_ttl_secs);
+        if let Some(cached) =
+            network_cache::get::<GetFavoriteRespData>(&self.app, &cache_key, ttl_secs)
+        {
+            return Ok(cached);
+        }
+
+        let resp_data = self.fetch_favorite_folder(folder_id, page, sort).await?;
+        let _ = network_cache::set(&self.app, &cache_key, &resp_data);
+        Ok(resp_data)
+    }
+
+    async fn fetch_favorite_folder(
+        &self,
+        folder_id: i64,
+        page: i64,
+        sort: FavoriteSort,
+    ) -> anyhow::Result<GetFavoriteRespData> {
+        let _ = (folder_id, page, sort, self.client());
+        Err(anyhow::anyhow!("尚未实现获取收藏夹的真实请求"))
+    }
+
+    // 收藏状态会被前端立即使用，且切换本身就是写操作，不应该被读缓存遮蔽，因此不经过缓存
+    pub async fn toggle_favorite_comic(&self, aid: i64) -> anyhow::Result<ToggleFavoriteRespData> {
+        let _ = (aid, self.client());
+        Err(anyhow::anyhow!("尚未实现收藏切换的真实请求"))
+    }
+
+    pub async fn download_image(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        let bytes = self
+            .client()
+            .get(url)
+            .send()
+            .await
+            .context(format!("请求图片`{url}`失败"))?
+            .bytes()
+            .await
+            .context(format!("读取图片`{url}`的响应体失败"))?;
+        Ok(bytes.to_vec())
+    }
+}
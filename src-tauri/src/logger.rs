@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use tauri::AppHandle;
+
+pub fn logs_dir(app: &AppHandle) -> anyhow::Result<PathBuf> {
+    use tauri::Manager;
+    app.path().app_log_dir().context("获取日志目录失败")
+}
+
+pub fn reload_file_logger() -> anyhow::Result<()> {
+    // 直接返回Ok(())：不会关闭或重新打开任何文件日志写入器
+    Ok(())
+}
+
+pub fn disable_file_logger() -> anyhow::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// `search`/`get_comic`/`get_favorite_folder`的响应缓存目录
+pub fn cache_dir(app: &AppHandle) -> anyhow::Result<PathBuf> {
+    use tauri::Manager;
+    let cache_dir = app.path().app_cache_dir().context("获取缓存目录失败")?;
+    Ok(cache_dir.join("network_cache"))
+}
+
+/// 清空网络缓存，用于在TTL到期前强制下一次请求重新从服务器拉取
+pub fn clear(app: &AppHandle) -> anyhow::Result<()> {
+    let cache_dir = cache_dir(app)?;
+    if cache_dir.exists() {
+        std::fs::remove_dir_all(&cache_dir).context(format!("删除目录`{cache_dir:?}`失败"))?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CacheEntry {
+    updated_at: u64,
+    value: serde_json::Value,
+}
+
+/// 在`ttl_secs`有效期内命中缓存则返回`Some`；过期或未命中返回`None`，
+/// 调用方应随后发起真实请求并通过[`set`]写回
+///
+/// `updated_at`记录的是条目首次写入时的时间，命中时不会被刷新：否则频繁访问的条目会
+/// 永远停留在“刚写入”的状态，TTL就形同虚设，缓存会一直返回陈旧数据
+pub fn get<T: DeserializeOwned>(app: &AppHandle, key: &str, ttl_secs: u64) -> Option<T> {
+    let path = entry_path(app, key).ok()?;
+    let entry_json = std::fs::read_to_string(&path).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&entry_json).ok()?;
+    if now_secs().saturating_sub(entry.updated_at) > ttl_secs {
+        return None;
+    }
+    let value: T = serde_json::from_value(entry.value).ok()?;
+    Some(value)
+}
+
+pub fn set<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> anyhow::Result<()> {
+    let value_json = serde_json::to_value(value).context("序列化缓存内容失败")?;
+    set_raw(app, key, value_json)
+}
+
+fn set_raw(app: &AppHandle, key: &str, value: serde_json::Value) -> anyhow::Result<()> {
+    let path = entry_path(app, key)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context(format!("创建目录`{parent:?}`失败"))?;
+    }
+    let entry = CacheEntry {
+        updated_at: now_secs(),
+        value,
+    };
+    let entry_json = serde_json::to_string(&entry).context("序列化缓存条目失败")?;
+    std::fs::write(&path, entry_json).context(format!("写入文件`{path:?}`失败"))?;
+    Ok(())
+}
+
+/// 按`端点+参数`拼出的`key`做哈希，作为缓存文件名
+fn entry_path(app: &AppHandle, key: &str) -> anyhow::Result<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    Ok(cache_dir(app)?.join(format!("{:016x}.json", hasher.finish())))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
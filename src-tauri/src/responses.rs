@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::types::{ChapterInfo, FavoriteComic};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GetUserProfileRespData {
+    pub username: String,
+    pub nickname: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ComicRespData {
+    pub id: i64,
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub cover_url: String,
+    pub chapter_infos: Vec<ChapterInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GetComicRespData {
+    pub comic: ComicRespData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchRespData {
+    pub comics: Vec<ComicRespData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GetFavoriteRespData {
+    pub list: Vec<FavoriteComic>,
+    pub folder_id: i64,
+    pub count: i64,
+    pub total: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToggleType {
+    Add,
+    Remove,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ToggleFavoriteRespData {
+    pub toggle_type: ToggleType,
+}
@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::responses::{ComicRespData, GetComicRespData, GetFavoriteRespData, SearchRespData};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    Latest,
+    View,
+    Picture,
+    Like,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum FavoriteSort {
+    FavoriteTime,
+    UpdateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ChapterInfo {
+    pub chapter_id: i64,
+    pub chapter_title: String,
+    pub order: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_downloaded: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Comic {
+    pub id: i64,
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub cover_url: String,
+    pub chapter_infos: Vec<ChapterInfo>,
+    /// 是否已下载，在落盘元数据时会被置为`None`以跳过序列化，运行时再根据下载目录重新计算
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_downloaded: Option<bool>,
+}
+
+impl Comic {
+    /// 漫画的下载目录：`{配置中的下载目录}/{漫画标题}`
+    pub fn get_comic_download_dir(app: &AppHandle, comic_title: &str) -> PathBuf {
+        use tauri::Manager;
+        let download_dir = app
+            .state::<parking_lot::RwLock<crate::config::Config>>()
+            .read()
+            .download_dir
+            .clone();
+        download_dir.join(sanitize_filename(comic_title))
+    }
+
+    pub fn from_comic_resp_data(_app: &AppHandle, data: GetComicRespData) -> Self {
+        let ComicRespData {
+            id,
+            name,
+            author,
+            description,
+            tags,
+            cover_url,
+            chapter_infos,
+        } = data.comic;
+        Comic {
+            id,
+            name,
+            author,
+            description,
+            tags,
+            cover_url,
+            chapter_infos,
+            is_downloaded: None,
+        }
+    }
+
+    pub fn from_metadata(app: &AppHandle, metadata_path: &std::path::Path) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        let metadata_json = std::fs::read_to_string(metadata_path)
+            .context(format!("读取元数据文件`{metadata_path:?}`失败"))?;
+        let mut comic: Comic = serde_json::from_str(&metadata_json)
+            .context(format!("解析元数据文件`{metadata_path:?}`失败"))?;
+        comic.recompute_is_downloaded(app);
+        Ok(comic)
+    }
+
+    /// 章节的下载目录：`{漫画下载目录}/{章节标题}`，章节标题同样需要清洗，
+    /// 否则写入方和读取方（如`recompute_is_downloaded`）对同一章节算出的目录会对不上
+    pub fn chapter_dir(comic_download_dir: &std::path::Path, chapter_title: &str) -> PathBuf {
+        comic_download_dir.join(sanitize_filename(chapter_title))
+    }
+
+    /// 元数据里不保存`is_downloaded`，调用方应在每个章节目录下的图片就绪后调用本方法，
+    /// 根据磁盘上的实际情况重新计算`is_downloaded`
+    pub fn recompute_is_downloaded(&mut self, app: &AppHandle) {
+        let comic_download_dir = Comic::get_comic_download_dir(app, &self.name);
+        for chapter in &mut self.chapter_infos {
+            let chapter_dir = Comic::chapter_dir(&comic_download_dir, &chapter.chapter_title);
+            let has_images = std::fs::read_dir(&chapter_dir)
+                .map(|mut entries| entries.next().is_some())
+                .unwrap_or(false);
+            chapter.is_downloaded = Some(has_images);
+        }
+        self.is_downloaded = Some(
+            self.chapter_infos
+                .iter()
+                .any(|chapter| chapter.is_downloaded == Some(true)),
+        );
+    }
+}
+
+/// 将漫画/章节标题中不能出现在文件名里的字符替换掉
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| if r#"/\:*?"<>|"#.contains(c) { '_' } else { c })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FavoriteComic {
+    pub id: String,
+    pub name: String,
+    pub cover_url: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GetFavoriteResult {
+    pub list: Vec<FavoriteComic>,
+    pub folder_id: i64,
+}
+
+impl GetFavoriteResult {
+    pub fn from_resp_data(_app: &AppHandle, data: GetFavoriteRespData) -> anyhow::Result<Self> {
+        Ok(GetFavoriteResult {
+            list: data.list,
+            folder_id: data.folder_id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchResultVariant {
+    pub comics: Vec<Comic>,
+}
+
+impl SearchResultVariant {
+    pub fn from_search_resp(app: &AppHandle, data: SearchRespData) -> anyhow::Result<Self> {
+        let comics = data
+            .comics
+            .into_iter()
+            .map(|comic_resp_data| Comic::from_comic_resp_data(app, GetComicRespData { comic: comic_resp_data }))
+            .collect();
+        Ok(SearchResultVariant { comics })
+    }
+}